@@ -1,11 +1,18 @@
 use anyhow::{Context, Result};
 use chrono::{DateTime, Utc};
-use git2::{Commit as Git2Commit, Oid, Repository};
+use git2::build::RepoBuilder;
+use git2::{Commit as Git2Commit, Delta, DiffFormat, DiffOptions, FetchOptions, Repository, Sort};
+use rand::rngs::StdRng;
 use rand::seq::SliceRandom;
-use std::path::Path;
+use rand::SeedableRng;
+use regex::Regex;
+use std::path::{Path, PathBuf};
 
 pub struct GitRepository {
     repo: Repository,
+    /// Set when this repository is a temporary clone we made ourselves, so
+    /// `Drop` knows to remove the directory; `None` for repos opened in place.
+    temp_dir: Option<PathBuf>,
 }
 
 #[derive(Debug, Clone)]
@@ -14,13 +21,249 @@ pub struct CommitMetadata {
     pub author: String,
     pub date: DateTime<Utc>,
     pub message: String,
+    /// The message parsed as a [Conventional Commit](https://www.conventionalcommits.org/),
+    /// or `None` if the header doesn't follow that grammar.
+    pub conventional: Option<ConventionalCommit>,
+}
+
+/// A commit message parsed per the Conventional Commits spec:
+/// `type(scope)!: description`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConventionalCommit {
+    pub type_: String,
+    pub scope: Option<String>,
+    pub breaking: bool,
+    pub description: String,
+}
+
+/// The diff introduced by a single commit, as returned by [`GitRepository::get_diff`].
+#[derive(Debug, Clone)]
+pub struct CommitDiff {
+    pub files: Vec<FileChange>,
+    pub patch: String,
+    pub stats: DiffStats,
+}
+
+/// One file's change within a [`CommitDiff`].
+#[derive(Debug, Clone)]
+pub struct FileChange {
+    pub old_path: Option<PathBuf>,
+    pub new_path: Option<PathBuf>,
+    pub status: ChangeStatus,
+}
+
+/// What happened to a file between the two sides of a diff.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChangeStatus {
+    Added,
+    Deleted,
+    Modified,
+    Renamed,
+    Copied,
+    Typechange,
+    Other,
+}
+
+impl From<Delta> for ChangeStatus {
+    fn from(delta: Delta) -> Self {
+        match delta {
+            Delta::Added => ChangeStatus::Added,
+            Delta::Deleted => ChangeStatus::Deleted,
+            Delta::Modified => ChangeStatus::Modified,
+            Delta::Renamed => ChangeStatus::Renamed,
+            Delta::Copied => ChangeStatus::Copied,
+            Delta::Typechange => ChangeStatus::Typechange,
+            _ => ChangeStatus::Other,
+        }
+    }
+}
+
+/// Aggregate change counts for a [`CommitDiff`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DiffStats {
+    pub files_changed: usize,
+    pub insertions: usize,
+    pub deletions: usize,
+}
+
+/// How [`GitRepository::random_commit_with`] weights candidates before sampling.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SamplingBias {
+    /// Every candidate is equally likely, as with [`GitRepository::random_commit`].
+    Uniform,
+    /// Candidates are weighted by an exponential decay over their age in days
+    /// relative to the newest commit in the pool, so recent commits are more
+    /// likely to be picked. `half_life_days` controls how quickly the weight
+    /// falls off: a commit `half_life_days` older than the newest one is half
+    /// as likely to be chosen.
+    Recency { half_life_days: f64 },
+}
+
+/// Sort order for commits returned by [`GitRepository::find_commits`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortOrder {
+    /// Children are shown before their parents, like `git log --topo-order`.
+    Topological,
+    /// Commits are ordered by commit time, newest first.
+    Date,
+}
+
+/// Builder describing a `git log`-style filter over a repository's commits.
+///
+/// Construct one with [`CommitQuery::new`], chain the filters you need, and
+/// pass it to [`GitRepository::find_commits`] (or [`GitRepository::random_commit`]
+/// to sample from the filtered pool).
+#[derive(Debug, Clone, Default)]
+pub struct CommitQuery {
+    author: Option<String>,
+    committer: Option<String>,
+    message_grep: Option<String>,
+    after: Option<DateTime<Utc>>,
+    before: Option<DateTime<Utc>>,
+    skip: usize,
+    max_count: Option<usize>,
+    min_parents: Option<usize>,
+    max_parents: Option<usize>,
+    sort: Option<SortOrder>,
+    reverse: bool,
+    paths: Vec<String>,
+}
+
+impl CommitQuery {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Keep only commits whose author name or email contains `author` (substring match).
+    pub fn author(mut self, author: impl Into<String>) -> Self {
+        self.author = Some(author.into());
+        self
+    }
+
+    /// Keep only commits whose committer name or email contains `committer` (substring match).
+    pub fn committer(mut self, committer: impl Into<String>) -> Self {
+        self.committer = Some(committer.into());
+        self
+    }
+
+    /// Keep only commits whose message matches `pattern` (a regex, as with `git log --grep`).
+    pub fn grep(mut self, pattern: impl Into<String>) -> Self {
+        self.message_grep = Some(pattern.into());
+        self
+    }
+
+    /// Keep only commits committed at or after this time.
+    pub fn after(mut self, after: DateTime<Utc>) -> Self {
+        self.after = Some(after);
+        self
+    }
+
+    /// Keep only commits committed at or before this time.
+    pub fn before(mut self, before: DateTime<Utc>) -> Self {
+        self.before = Some(before);
+        self
+    }
+
+    /// Skip this many matching commits before returning results.
+    pub fn skip(mut self, skip: usize) -> Self {
+        self.skip = skip;
+        self
+    }
+
+    /// Return at most this many commits.
+    pub fn max_count(mut self, max_count: usize) -> Self {
+        self.max_count = Some(max_count);
+        self
+    }
+
+    /// Keep only commits with at least this many parents (`1` excludes root commits).
+    pub fn min_parents(mut self, min_parents: usize) -> Self {
+        self.min_parents = Some(min_parents);
+        self
+    }
+
+    /// Keep only commits with at most this many parents (`1` excludes merges).
+    pub fn max_parents(mut self, max_parents: usize) -> Self {
+        self.max_parents = Some(max_parents);
+        self
+    }
+
+    /// Shorthand for `max_parents(1)`, excluding merge commits.
+    pub fn no_merges(self) -> Self {
+        self.max_parents(1)
+    }
+
+    /// Order results topologically (default) or by commit date.
+    pub fn sort_by(mut self, sort: SortOrder) -> Self {
+        self.sort = Some(sort);
+        self
+    }
+
+    /// Reverse whichever sort order is in effect.
+    pub fn reverse(mut self, reverse: bool) -> Self {
+        self.reverse = reverse;
+        self
+    }
+
+    /// Keep only commits whose diff against their first parent touches one of `paths`.
+    pub fn paths<I, S>(mut self, paths: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.paths = paths.into_iter().map(Into::into).collect();
+        self
+    }
+
+    fn sort_flags(&self) -> Sort {
+        let mut flags = match self.sort.unwrap_or(SortOrder::Topological) {
+            SortOrder::Topological => Sort::TOPOLOGICAL,
+            SortOrder::Date => Sort::TIME,
+        };
+        if self.reverse {
+            flags |= Sort::REVERSE;
+        }
+        flags
+    }
 }
 
 impl GitRepository {
     pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
         let repo = Repository::open(path)
             .context("Failed to open Git repository")?;
-        Ok(Self { repo })
+        Ok(Self {
+            repo,
+            temp_dir: None,
+        })
+    }
+
+    /// Shallow-clone a remote repository (HTTPS or SSH URL) into a temporary
+    /// directory and open it, so `gitlogue` can be pointed at any public
+    /// repository without the user cloning it first. The clone is removed
+    /// when the returned `GitRepository` is dropped.
+    ///
+    /// No credentials are supplied to the clone, so SSH remotes (and HTTPS
+    /// remotes) that require authentication fail to clone.
+    pub fn open_remote(url: &str) -> Result<Self> {
+        let nanos = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_nanos();
+        let temp_dir =
+            std::env::temp_dir().join(format!("gitlogue-{}-{}", std::process::id(), nanos));
+
+        let mut fetch_options = FetchOptions::new();
+        fetch_options.depth(1);
+
+        let repo = RepoBuilder::new()
+            .fetch_options(fetch_options)
+            .clone(url, &temp_dir)
+            .with_context(|| format!("Failed to clone remote repository: {url}"))?;
+
+        Ok(Self {
+            repo,
+            temp_dir: Some(temp_dir),
+        })
     }
 
     pub fn get_commit(&self, hash: &str) -> Result<CommitMetadata> {
@@ -35,30 +278,247 @@ impl GitRepository {
         Ok(Self::extract_metadata(&commit))
     }
 
-    pub fn random_commit(&self) -> Result<CommitMetadata> {
-        let mut revwalk = self.repo.revwalk()?;
-        revwalk.push_head()?;
+    /// Compute the diff introduced by `hash` against its first parent (or the
+    /// empty tree, for a root commit). Kept separate from [`Self::get_commit`]
+    /// so callers only pay the diff cost when they actually want the patch.
+    pub fn get_diff(&self, hash: &str) -> Result<CommitDiff> {
+        let obj = self.repo
+            .revparse_single(hash)
+            .context("Invalid commit hash or commit not found")?;
+        let commit = obj
+            .peel_to_commit()
+            .context("Object is not a commit")?;
+
+        let tree = commit.tree()?;
+        let parent_tree = match commit.parent(0) {
+            Ok(parent) => Some(parent.tree()?),
+            Err(_) => None,
+        };
+
+        let diff = self
+            .repo
+            .diff_tree_to_tree(parent_tree.as_ref(), Some(&tree), None)?;
 
-        let non_merge_commits: Vec<Oid> = revwalk
-            .filter_map(|oid| oid.ok())
-            .filter(|oid| {
-                self.repo
-                    .find_commit(*oid)
-                    .map(|c| c.parent_count() <= 1)
-                    .unwrap_or(false)
+        let files = diff
+            .deltas()
+            .map(|delta| FileChange {
+                old_path: delta.old_file().path().map(|p| p.to_path_buf()),
+                new_path: delta.new_file().path().map(|p| p.to_path_buf()),
+                status: ChangeStatus::from(delta.status()),
             })
             .collect();
 
-        if non_merge_commits.is_empty() {
-            anyhow::bail!("No non-merge commits found in repository");
+        let mut patch = String::new();
+        diff.print(DiffFormat::Patch, |_, _, line| {
+            let origin = line.origin();
+            if origin == '+' || origin == '-' || origin == ' ' {
+                patch.push(origin);
+            }
+            patch.push_str(&String::from_utf8_lossy(line.content()));
+            true
+        })?;
+
+        let diff_stats = diff.stats()?;
+        let stats = DiffStats {
+            files_changed: diff_stats.files_changed(),
+            insertions: diff_stats.insertions(),
+            deletions: diff_stats.deletions(),
+        };
+
+        Ok(CommitDiff {
+            files,
+            patch,
+            stats,
+        })
+    }
+
+    /// Walk the repository's history and return every commit matching `query`.
+    pub fn find_commits(&self, query: &CommitQuery) -> Result<Vec<CommitMetadata>> {
+        let mut revwalk = self.repo.revwalk()?;
+        revwalk.push_head()?;
+        revwalk.set_sorting(query.sort_flags())?;
+
+        let grep = query
+            .message_grep
+            .as_deref()
+            .map(Regex::new)
+            .transpose()
+            .context("Invalid --grep pattern")?;
+
+        let mut matches = Vec::new();
+        for oid in revwalk {
+            let oid = oid?;
+            let commit = self.repo.find_commit(oid)?;
+
+            if !self.commit_matches(&commit, query, grep.as_ref())? {
+                continue;
+            }
+
+            matches.push(commit.id());
+
+            if let Some(max_count) = query.max_count {
+                if matches.len() >= query.skip + max_count {
+                    break;
+                }
+            }
+        }
+
+        matches
+            .into_iter()
+            .skip(query.skip)
+            .take(query.max_count.unwrap_or(usize::MAX))
+            .map(|oid| Ok(Self::extract_metadata(&self.repo.find_commit(oid)?)))
+            .collect()
+    }
+
+    fn commit_matches(
+        &self,
+        commit: &Git2Commit,
+        query: &CommitQuery,
+        grep: Option<&Regex>,
+    ) -> Result<bool> {
+        let parent_count = commit.parent_count();
+        if let Some(min_parents) = query.min_parents {
+            if parent_count < min_parents {
+                return Ok(false);
+            }
+        }
+        if let Some(max_parents) = query.max_parents {
+            if parent_count > max_parents {
+                return Ok(false);
+            }
+        }
+
+        if let Some(author) = &query.author {
+            let a = commit.author();
+            let haystack = format!("{} {}", a.name().unwrap_or(""), a.email().unwrap_or(""));
+            if !haystack.contains(author.as_str()) {
+                return Ok(false);
+            }
         }
 
-        let oid = non_merge_commits
-            .choose(&mut rand::thread_rng())
-            .context("Failed to select random commit")?;
+        if let Some(committer) = &query.committer {
+            let c = commit.committer();
+            let haystack = format!("{} {}", c.name().unwrap_or(""), c.email().unwrap_or(""));
+            if !haystack.contains(committer.as_str()) {
+                return Ok(false);
+            }
+        }
 
-        let commit = self.repo.find_commit(*oid)?;
-        Ok(Self::extract_metadata(&commit))
+        if let Some(grep) = grep {
+            if !grep.is_match(commit.message().unwrap_or("")) {
+                return Ok(false);
+            }
+        }
+
+        let committed_at =
+            DateTime::from_timestamp(commit.time().seconds(), 0).unwrap_or_else(Utc::now);
+        if let Some(after) = query.after {
+            if committed_at < after {
+                return Ok(false);
+            }
+        }
+        if let Some(before) = query.before {
+            if committed_at > before {
+                return Ok(false);
+            }
+        }
+
+        if !query.paths.is_empty() && !self.touches_paths(commit, &query.paths)? {
+            return Ok(false);
+        }
+
+        Ok(true)
+    }
+
+    fn touches_paths(&self, commit: &Git2Commit, paths: &[String]) -> Result<bool> {
+        let tree = commit.tree()?;
+        let parent_tree = match commit.parent(0) {
+            Ok(parent) => Some(parent.tree()?),
+            Err(_) => None,
+        };
+
+        let mut diff_opts = DiffOptions::new();
+        for path in paths {
+            diff_opts.pathspec(path);
+        }
+
+        let diff =
+            self.repo
+                .diff_tree_to_tree(parent_tree.as_ref(), Some(&tree), Some(&mut diff_opts))?;
+
+        Ok(diff.deltas().len() > 0)
+    }
+
+    /// Pick a uniformly random commit, optionally restricted by `query`.
+    ///
+    /// With no query, this keeps the historical behavior of excluding merges.
+    pub fn random_commit(&self, query: Option<&CommitQuery>) -> Result<CommitMetadata> {
+        let default_query;
+        let query = match query {
+            Some(query) => query,
+            None => {
+                default_query = CommitQuery::new().no_merges();
+                &default_query
+            }
+        };
+
+        let pool = self.find_commits(query)?;
+        if pool.is_empty() {
+            anyhow::bail!("No commits matched the given query");
+        }
+
+        pool.choose(&mut rand::thread_rng())
+            .cloned()
+            .context("Failed to select random commit")
+    }
+
+    /// Like [`Self::random_commit`], but with a reproducible `seed` and a
+    /// [`SamplingBias`] controlling how candidates are weighted.
+    ///
+    /// Passing the same `seed` and `query` always selects the same commit,
+    /// which is handy for tests and demos; `seed: None` falls back to
+    /// non-deterministic selection.
+    pub fn random_commit_with(
+        &self,
+        query: Option<&CommitQuery>,
+        seed: Option<u64>,
+        bias: SamplingBias,
+    ) -> Result<CommitMetadata> {
+        let default_query;
+        let query = match query {
+            Some(query) => query,
+            None => {
+                default_query = CommitQuery::new().no_merges();
+                &default_query
+            }
+        };
+
+        let pool = self.find_commits(query)?;
+        if pool.is_empty() {
+            anyhow::bail!("No commits matched the given query");
+        }
+
+        let mut rng = match seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_entropy(),
+        };
+
+        let selected = match bias {
+            SamplingBias::Uniform => pool.choose(&mut rng),
+            SamplingBias::Recency { half_life_days } => {
+                let newest = pool.iter().map(|c| c.date).max().unwrap_or_else(Utc::now);
+                pool.choose_weighted(&mut rng, |commit| {
+                    let age_days = (newest - commit.date).num_seconds() as f64 / 86_400.0;
+                    0.5f64.powf(age_days.max(0.0) / half_life_days.max(f64::EPSILON))
+                })
+                .ok()
+            }
+        };
+
+        selected
+            .cloned()
+            .context("Failed to select random commit")
     }
 
     fn extract_metadata(commit: &Git2Commit) -> CommitMetadata {
@@ -69,12 +529,67 @@ impl GitRepository {
         let date = DateTime::from_timestamp(timestamp, 0)
             .unwrap_or_else(|| Utc::now());
         let message = commit.message().unwrap_or("").trim().to_string();
+        let conventional = parse_conventional(&message);
 
         CommitMetadata {
             hash,
             author: author_name,
             date,
             message,
+            conventional,
+        }
+    }
+}
+
+/// Parse a commit message's header against the Conventional Commits grammar
+/// `type(scope)!: description`, returning `None` when it doesn't match.
+fn parse_conventional(message: &str) -> Option<ConventionalCommit> {
+    let header = message.lines().next()?.trim();
+    let colon = header.find(':')?;
+    let (prefix, rest) = header.split_at(colon);
+    let description = rest[1..].trim();
+    if description.is_empty() {
+        return None;
+    }
+
+    let (prefix, mut breaking) = match prefix.strip_suffix('!') {
+        Some(prefix) => (prefix, true),
+        None => (prefix, false),
+    };
+
+    let (type_, scope) = match prefix.find('(') {
+        Some(open) if prefix.ends_with(')') => {
+            let type_ = &prefix[..open];
+            let scope = &prefix[open + 1..prefix.len() - 1];
+            (type_, Some(scope))
+        }
+        Some(_) => return None,
+        None => (prefix, None),
+    };
+
+    if type_.is_empty() || !type_.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+        return None;
+    }
+
+    if message
+        .lines()
+        .any(|line| line.trim_start().starts_with("BREAKING CHANGE:"))
+    {
+        breaking = true;
+    }
+
+    Some(ConventionalCommit {
+        type_: type_.to_string(),
+        scope: scope.map(|s| s.to_string()),
+        breaking,
+        description: description.to_string(),
+    })
+}
+
+impl Drop for GitRepository {
+    fn drop(&mut self) {
+        if let Some(temp_dir) = &self.temp_dir {
+            let _ = std::fs::remove_dir_all(temp_dir);
         }
     }
 }